@@ -0,0 +1,351 @@
+//! Derive macros for `bcder`.
+//!
+//! This crate is not meant to be used directly. Instead, enable the
+//! `derive` feature of the `bcder` crate, which re-exports `Encode` and
+//! `Decode` from here.
+//!
+//! `#[derive(Encode)]` generates an `encode`/`encode_as` method pair that
+//! returns a `Values` value encoding the struct’s fields, in declaration
+//! order, as the content of a SEQUENCE. `#[derive(Decode)]` generates
+//! `take_from`/`take_content_from` methods that read the fields back in
+//! the same order from inside `Constructed::take_sequence`.
+//!
+//! Both derives understand a `#[ber(..)]` attribute on struct fields:
+//!
+//! * `#[ber(tag = "context(0)")]` -- the field is wrapped in an
+//!   additional constructed value carrying the given tag (EXPLICIT
+//!   tagging) instead of using its own, natural tag.
+//! * `#[ber(tag = "context(0)", implicit)]` -- the field’s natural tag is
+//!   replaced by the given tag (IMPLICIT tagging) rather than wrapped in
+//!   it, via `bcder::encode::implicit`. Currently only supported for
+//!   fields whose type implements `PrimitiveContent`.
+//! * `#[ber(default = "path::to::fn")]` -- the field is OPTIONAL DEFAULT:
+//!   it is omitted from the encoding when it equals the value returned
+//!   by the given, no-argument function, and decoded as that value when
+//!   the field is absent.
+//!
+//! A field of type `Option<T>` is treated as plain OPTIONAL, reusing
+//! `bcder`’s blanket `Values` impl for `Option`.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Fields, Lit, Meta, NestedMeta,
+    Path, Type,
+};
+
+
+//------------ derive(Encode) --------------------------------------------------
+
+#[proc_macro_derive(Encode, attributes(ber))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match encode_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn encode_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+    let fields = named_fields(input)?;
+    let attrs = fields.iter().map(|f| FieldAttr::from_field(f))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let encoders: Vec<_> = fields.iter().zip(attrs.iter())
+        .map(|(field, attr)| attr.encode_field(field))
+        .collect();
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns a value encoder for this value.
+            ///
+            /// The returned encoder produces a SEQUENCE containing the
+            /// struct’s fields, in declaration order, as its content.
+            pub fn encode(&self) -> impl ::bcder::encode::Values + '_ {
+                ::bcder::encode::sequence(( #( #encoders, )* ))
+            }
+
+            /// Returns a value encoder for this value using `tag`.
+            ///
+            /// This is identical to `encode`, except the constructed
+            /// value carries `tag` instead of the universal SEQUENCE tag.
+            pub fn encode_as(
+                &self, tag: ::bcder::Tag
+            ) -> impl ::bcder::encode::Values + '_ {
+                ::bcder::encode::sequence_as(tag, ( #( #encoders, )* ))
+            }
+        }
+    })
+}
+
+
+//------------ derive(Decode) --------------------------------------------------
+
+#[proc_macro_derive(Decode, attributes(ber))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match decode_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn decode_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+    let fields = named_fields(input)?;
+    let attrs = fields.iter().map(|f| FieldAttr::from_field(f))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let field_idents: Vec<_> =
+        fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let field_decoders: Vec<_> = fields.iter().zip(attrs.iter())
+        .map(|(field, attr)| attr.decode_field(field))
+        .collect();
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Takes a value of this type from the beginning of `cons`.
+            pub fn take_from<S: ::bcder::decode::Source>(
+                cons: &mut ::bcder::decode::Constructed<S>
+            ) -> Result<Self, S::Err> {
+                cons.take_sequence(Self::take_content_from)
+            }
+
+            /// Parses the content octets of a SEQUENCE of this type.
+            pub fn take_content_from<S: ::bcder::decode::Source>(
+                cons: &mut ::bcder::decode::Constructed<S>
+            ) -> Result<Self, S::Err> {
+                #( #field_decoders )*
+                Ok(#name { #( #field_idents, )* })
+            }
+        }
+    })
+}
+
+
+//------------ Shared Helpers --------------------------------------------------
+
+/// Returns the named fields of a struct, erroring out on anything else.
+fn named_fields(input: &DeriveInput) -> syn::Result<Vec<&syn::Field>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().collect()),
+            _ => Err(syn::Error::new(
+                input.span(),
+                "Encode/Decode can only be derived for structs with \
+                 named fields",
+            )),
+        },
+        _ => Err(syn::Error::new(
+            input.span(),
+            "Encode/Decode can only be derived for structs",
+        )),
+    }
+}
+
+/// The parsed content of a field’s `#[ber(..)]` attribute, if any.
+#[derive(Default)]
+struct FieldAttr {
+    /// An explicit or implicit tag to use instead of the field’s own.
+    tag: Option<Path>,
+
+    /// Whether `tag` replaces the field’s tag rather than wrapping it.
+    implicit: bool,
+
+    /// A no-argument function path producing the DEFAULT value.
+    default: Option<Path>,
+}
+
+impl FieldAttr {
+    fn from_field(field: &syn::Field) -> syn::Result<Self> {
+        let mut res = FieldAttr::default();
+        let mut implicit_span = None;
+        for attr in &field.attrs {
+            if !attr.path.is_ident("ber") {
+                continue
+            }
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(syn::Error::new(
+                    meta.span(), "expected #[ber(..)]"
+                )),
+            };
+            for nested in list.nested.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv))
+                    if nv.path.is_ident("tag") => {
+                        res.tag = Some(lit_str_to_tag_path(&nv.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv))
+                    if nv.path.is_ident("default") => {
+                        res.default = Some(lit_str_to_path(&nv.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::Path(path))
+                    if path.is_ident("implicit") => {
+                        res.implicit = true;
+                        implicit_span = Some(path.span());
+                    }
+                    other => return Err(syn::Error::new(
+                        other.span(), "unrecognized `ber` argument"
+                    )),
+                }
+            }
+        }
+        if let Some(span) = implicit_span {
+            if res.tag.is_none() {
+                return Err(syn::Error::new(
+                    span, "`implicit` requires `tag`"
+                ))
+            }
+        }
+        Ok(res)
+    }
+
+    /// Builds the encoder expression for `field`.
+    fn encode_field(&self, field: &syn::Field) -> TokenStream2 {
+        let ident = field.ident.as_ref().unwrap();
+        let span = field.span();
+        let is_option = is_option_type(&field.ty);
+
+        // Wraps a reference expression `v` according to `self.tag`.
+        let wrap = |v: TokenStream2| -> TokenStream2 {
+            match (&self.tag, self.implicit) {
+                (Some(tag), true) => quote_spanned!(span=>
+                    ::bcder::encode::implicit(#tag, #v)
+                ),
+                (Some(tag), false) => quote_spanned!(span=>
+                    (#v).encode().explicit(#tag)
+                ),
+                (None, _) => quote_spanned!(span=> (#v).encode()),
+            }
+        };
+
+        if is_option {
+            let inner = wrap(quote_spanned!(span=> v));
+            quote_spanned!(span=> self.#ident.as_ref().map(|v| #inner))
+        }
+        else if let Some(default) = &self.default {
+            let inner = wrap(quote_spanned!(span=> &self.#ident));
+            quote_spanned!(span=>
+                if self.#ident == #default() { None } else { Some(#inner) }
+            )
+        }
+        else {
+            wrap(quote_spanned!(span=> &self.#ident))
+        }
+    }
+
+    /// Builds the `let #ident = ..;` decoding statement for `field`.
+    fn decode_field(&self, field: &syn::Field) -> TokenStream2 {
+        let ident = field.ident.as_ref().unwrap();
+        let span = field.span();
+        let is_option = is_option_type(&field.ty);
+        let optional = is_option || self.default.is_some();
+        let ty: &Type = if is_option {
+            option_inner_type(&field.ty).unwrap_or(&field.ty)
+        }
+        else {
+            &field.ty
+        };
+
+        let prefix = if optional { "take_opt" } else { "take" };
+        let call = match (&self.tag, self.implicit) {
+            (Some(tag), true) => {
+                let method = format_ident!("{}_primitive_if", prefix);
+                quote_spanned!(span=>
+                    cons.#method(#tag, #ty::take_content_from)?
+                )
+            }
+            (Some(tag), false) => {
+                let method = format_ident!("{}_constructed_if", prefix);
+                quote_spanned!(span=>
+                    cons.#method(#tag, #ty::take_from)?
+                )
+            }
+            (None, _) => {
+                let method = format_ident!("{}_from", prefix);
+                quote_spanned!(span=> #ty::#method(cons)?)
+            }
+        };
+
+        let value = match &self.default {
+            Some(default) => quote_spanned!(span=>
+                #call.unwrap_or_else(#default)
+            ),
+            None => call,
+        };
+
+        quote_spanned!(span=> let #ident = #value;)
+    }
+}
+
+/// Whether `ty` is an `Option<_>`.
+fn is_option_type(ty: &Type) -> bool {
+    option_inner_type(ty).is_some()
+}
+
+/// Returns the inner type `T` if `ty` is `Option<T>`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) => &path.path,
+        _ => return None,
+    };
+    let seg = path.segments.last()?;
+    if seg.ident != "Option" {
+        return None
+    }
+    match &seg.arguments {
+        syn::PathArguments::AngleBracketed(args) => {
+            match args.args.first()? {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Parses a function path given as a string literal.
+fn lit_str_to_path(lit: &Lit) -> syn::Result<Path> {
+    let lit = match lit {
+        Lit::Str(lit) => lit,
+        _ => return Err(syn::Error::new(
+            lit.span(), "expected a string literal"
+        )),
+    };
+    lit.parse()
+}
+
+/// Parses a tag literal such as `"context(0)"` into a `Tag`-returning
+/// expression path, e.g. `::bcder::Tag::ctxt(0)`.
+fn lit_str_to_tag_path(lit: &Lit) -> syn::Result<Path> {
+    let lit = match lit {
+        Lit::Str(lit) => lit,
+        _ => return Err(syn::Error::new(
+            lit.span(), "expected a string literal"
+        )),
+    };
+    let value = lit.value();
+    let (class, number) = value.split_once('(').ok_or_else(|| {
+        syn::Error::new(
+            lit.span(), "expected a tag of the form `context(0)`"
+        )
+    })?;
+    let number: u32 = number.trim_end_matches(')').parse().map_err(|_| {
+        syn::Error::new(lit.span(), "expected a numeric tag value")
+    })?;
+    let ctor = match class {
+        "context" => "ctxt",
+        other => other,
+    };
+    syn::parse_str(&format!("::bcder::Tag::{}({})", ctor, number))
+}
@@ -8,6 +8,7 @@ use ::captured::Captured;
 use ::length::Length;
 use ::mode::Mode;
 use ::tag::Tag;
+use super::PrimitiveContent;
 
 
 //------------ Values --------------------------------------------------------
@@ -70,58 +71,51 @@ impl<'a, T: Values> Values for &'a T {
 
 
 //--- Impls for Tuples
-
-impl<T: Values, U: Values> Values for (T, U) {
-    fn encoded_len(&self, mode: Mode) -> usize {
-        self.0.encoded_len(mode) + self.1.encoded_len(mode)
-    }
-
-    fn write_encoded<W: io::Write>(
-        &self,
-        mode: Mode,
-        target: &mut W
-    ) -> Result<(), io::Error> {
-        self.0.write_encoded(mode, target)?;
-        self.1.write_encoded(mode, target)?;
-        Ok(())
-    }
-}
-
-impl<R: Values, S: Values, T: Values> Values for (R, S, T) {
-    fn encoded_len(&self, mode: Mode) -> usize {
-        self.0.encoded_len(mode) + self.1.encoded_len(mode)
-        + self.2.encoded_len(mode)
-    }
-
-    fn write_encoded<W: io::Write>(
-        &self,
-        mode: Mode,
-        target: &mut W
-    ) -> Result<(), io::Error> {
-        self.0.write_encoded(mode, target)?;
-        self.1.write_encoded(mode, target)?;
-        self.2.write_encoded(mode, target)?;
-        Ok(())
+//
+// Rather than writing out an impl for every arity by hand, a declarative
+// macro generates `Values` for tuples from 1-tuples up to 12-tuples. Each
+// arm lists the tuple’s length together with its field indexes and type
+// parameters, since macro_rules has no way to derive one from the other.
+
+macro_rules! tuple_values {
+    ( $( $len:expr => ( $($n:tt $ty:ident)+ ) )+ ) => {
+        $(
+            impl<$($ty: Values),+> Values for ($($ty,)+) {
+                fn encoded_len(&self, mode: Mode) -> usize {
+                    0 $( + self.$n.encoded_len(mode) )+
+                }
+
+                fn write_encoded<W: io::Write>(
+                    &self,
+                    mode: Mode,
+                    target: &mut W
+                ) -> Result<(), io::Error> {
+                    $( self.$n.write_encoded(mode, target)?; )+
+                    Ok(())
+                }
+            }
+        )+
     }
 }
 
-impl<R: Values, S: Values, T: Values, U: Values> Values for (R, S, T, U) {
-    fn encoded_len(&self, mode: Mode) -> usize {
-        self.0.encoded_len(mode) + self.1.encoded_len(mode)
-        + self.2.encoded_len(mode) + self.3.encoded_len(mode)
-    }
-
-    fn write_encoded<W: io::Write>(
-        &self,
-        mode: Mode,
-        target: &mut W
-    ) -> Result<(), io::Error> {
-        self.0.write_encoded(mode, target)?;
-        self.1.write_encoded(mode, target)?;
-        self.2.write_encoded(mode, target)?;
-        self.3.write_encoded(mode, target)?;
-        Ok(())
-    }
+tuple_values! {
+    1  => (0 T0)
+    2  => (0 T0 1 T1)
+    3  => (0 T0 1 T1 2 T2)
+    4  => (0 T0 1 T1 2 T2 3 T3)
+    5  => (0 T0 1 T1 2 T2 3 T3 4 T4)
+    6  => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5)
+    7  => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6)
+    8  => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7)
+    9  => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8)
+    10 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9)
+    11 => (
+        0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10
+    )
+    12 => (
+        0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10
+        11 T11
+    )
 }
 
 
@@ -243,26 +237,25 @@ impl<V: Values> Values for Constructed<V> {
 }
 
 
-//------------ Choice2 -------------------------------------------------------
+//------------ Implicit -------------------------------------------------------
 
-/// A value encoder for a two-variant enum.
+/// A value encoder for an IMPLICIT-tagged primitive value.
 ///
-/// Instead of implementing `Values` for an enum manually, you can just
-/// define a method `encode` that returns a value of this type.
-pub enum Choice2<L, R> {
-    /// The first choice.
-    One(L),
-
-    /// The second choice.
-    Two(R)
+/// Unlike `Constructed`, which always produces a constructed value, this
+/// writes `inner`’s content octets straight after the new identifier and
+/// length octets, exactly as if `inner` had been defined with `tag` to
+/// begin with.
+struct Implicit<V> {
+    /// The tag to encode instead of `inner`’s own.
+    tag: Tag,
+
+    /// The primitive content to encode.
+    inner: V,
 }
 
-impl<L: Values, R: Values> Values for Choice2<L, R> {
+impl<V: PrimitiveContent> Values for Implicit<V> {
     fn encoded_len(&self, mode: Mode) -> usize {
-        match *self {
-            Choice2::One(ref inner) => inner.encoded_len(mode),
-            Choice2::Two(ref inner) => inner.encoded_len(mode),
-        }
+        total_encoded_len(self.tag, self.inner.encoded_len(mode))
     }
 
     fn write_encoded<W: io::Write>(
@@ -270,53 +263,123 @@ impl<L: Values, R: Values> Values for Choice2<L, R> {
         mode: Mode,
         target: &mut W
     ) -> Result<(), io::Error> {
-        match *self {
-            Choice2::One(ref inner) => inner.write_encoded(mode, target),
-            Choice2::Two(ref inner) => inner.write_encoded(mode, target),
-        }
+        write_header(
+            target, self.tag, false, self.inner.encoded_len(mode)
+        )?;
+        self.inner.write_encoded(mode, target)
     }
 }
 
 
-//------------ Choice3 -------------------------------------------------------
-
-/// A value encoder for a three-variant enum.
-///
-/// Instead of implementing `Values` for an enum manually, you can just
-/// define a method `encode` that returns a value of this type.
-pub enum Choice3<L, C, R> {
-    /// The first choice.
-    One(L),
-
-    /// The second choice.
-    Two(C),
-
-    /// The third choice.
-    Three(R)
-}
-
-impl<L: Values, C: Values, R: Values> Values for Choice3<L, C, R> {
-    fn encoded_len(&self, mode: Mode) -> usize {
-        match *self {
-            Choice3::One(ref inner) => inner.encoded_len(mode),
-            Choice3::Two(ref inner) => inner.encoded_len(mode),
-            Choice3::Three(ref inner) => inner.encoded_len(mode),
+//------------ ChoiceN --------------------------------------------------------
+//
+// `Choice2` and `Choice3` used to be hand-written, but real CHOICE types
+// regularly have more variants than that, and the boilerplate for each
+// additional one is identical. A declarative macro generates `Choice2`
+// through `Choice16` instead; each dispatches `encoded_len`/
+// `write_encoded` to whichever variant is active, exactly as before.
+
+macro_rules! choice {
+    ( $name:ident; $( $var:ident($ty:ident) ),+ $(,)? ) => {
+        /// A value encoder for an enum of alternative values.
+        ///
+        /// Instead of implementing `Values` for an enum manually, you
+        /// can just define a method `encode` that returns a value of
+        /// this type.
+        pub enum $name<$($ty),+> {
+            $( $var($ty) ),+
         }
-    }
 
-    fn write_encoded<W: io::Write>(
-        &self,
-        mode: Mode,
-        target: &mut W
-    ) -> Result<(), io::Error> {
-        match *self {
-            Choice3::One(ref inner) => inner.write_encoded(mode, target),
-            Choice3::Two(ref inner) => inner.write_encoded(mode, target),
-            Choice3::Three(ref inner) => inner.write_encoded(mode, target),
+        impl<$($ty: Values),+> Values for $name<$($ty),+> {
+            fn encoded_len(&self, mode: Mode) -> usize {
+                match *self {
+                    $( $name::$var(ref inner) => inner.encoded_len(mode), )+
+                }
+            }
+
+            fn write_encoded<W: io::Write>(
+                &self,
+                mode: Mode,
+                target: &mut W
+            ) -> Result<(), io::Error> {
+                match *self {
+                    $(
+                        $name::$var(ref inner) => {
+                            inner.write_encoded(mode, target)
+                        }
+                    )+
+                }
+            }
         }
     }
 }
 
+choice!(Choice2; One(L0), Two(L1));
+choice!(Choice3; One(L0), Two(L1), Three(L2));
+choice!(Choice4; One(L0), Two(L1), Three(L2), Four(L3));
+choice!(
+    Choice5;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4)
+);
+choice!(
+    Choice6;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5)
+);
+choice!(
+    Choice7;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6)
+);
+choice!(
+    Choice8;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7)
+);
+choice!(
+    Choice9;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7), Nine(L8)
+);
+choice!(
+    Choice10;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7), Nine(L8), Ten(L9)
+);
+choice!(
+    Choice11;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7), Nine(L8), Ten(L9), Eleven(L10)
+);
+choice!(
+    Choice12;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7), Nine(L8), Ten(L9), Eleven(L10), Twelve(L11)
+);
+choice!(
+    Choice13;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7), Nine(L8), Ten(L9), Eleven(L10), Twelve(L11),
+    Thirteen(L12)
+);
+choice!(
+    Choice14;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7), Nine(L8), Ten(L9), Eleven(L10), Twelve(L11),
+    Thirteen(L12), Fourteen(L13)
+);
+choice!(
+    Choice15;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7), Nine(L8), Ten(L9), Eleven(L10), Twelve(L11),
+    Thirteen(L12), Fourteen(L13), Fifteen(L14)
+);
+choice!(
+    Choice16;
+    One(L0), Two(L1), Three(L2), Four(L3), Five(L4), Six(L5),
+    Seven(L6), Eight(L7), Nine(L8), Ten(L9), Eleven(L10), Twelve(L11),
+    Thirteen(L12), Fourteen(L13), Fifteen(L14), Sixteen(L15)
+);
+
 
 //--------------- Iter -------------------------------------------------------
 
@@ -451,6 +514,28 @@ pub fn write_header<W: io::Write>(
     Ok(())
 }
 
+/// Returns a value encoder for an OPTIONAL DEFAULT value.
+///
+/// The DER canonical form of a value defined as `OPTIONAL DEFAULT
+/// default_value` omits the value entirely when it equals the default.
+/// This function encodes `value` normally unless it equals
+/// `default_value`, in which case it encodes nothing -- exactly like the
+/// existing `Option` impl, which it is built upon.
+pub fn default<V: Values + PartialEq>(
+    value: V, default_value: V
+) -> impl Values {
+    if value == default_value { None } else { Some(value) }
+}
+
+/// Returns a value encoder that re-tags `inner` in place.
+///
+/// Unlike `Values::explicit`, which wraps `inner` in an additional
+/// constructed value carrying the new tag, this replaces `inner`’s own
+/// tag with `tag` -- the `[n] IMPLICIT` form of tagging.
+pub fn implicit<V: PrimitiveContent>(tag: Tag, inner: V) -> impl Values {
+    Implicit { tag, inner }
+}
+
 
 //============ Helper Types ==================================================
 
@@ -473,3 +558,65 @@ impl Values for EndOfValue {
         target.write_all(&buf)
     }
 }
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `Values` impl around a fixed byte slice, for assembling expected
+    /// encodings in tests without depending on any other encoder.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Raw(&'static [u8]);
+
+    impl Values for Raw {
+        fn encoded_len(&self, _mode: Mode) -> usize {
+            self.0.len()
+        }
+
+        fn write_encoded<W: io::Write>(
+            &self, _mode: Mode, target: &mut W
+        ) -> Result<(), io::Error> {
+            target.write_all(self.0)
+        }
+    }
+
+    fn encode<V: Values>(value: V) -> Vec<u8> {
+        let mut buf = Vec::new();
+        value.write_encoded(Mode::Der, &mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn tuple_concatenates_in_order() {
+        assert_eq!(
+            encode((Raw(&[1, 2]), Raw(&[3]), Raw(&[4, 5, 6]))),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn choice_encodes_active_variant() {
+        let one: Choice2<Raw, Raw> = Choice2::One(Raw(&[1, 2]));
+        let two: Choice2<Raw, Raw> = Choice2::Two(Raw(&[9]));
+        assert_eq!(encode(one), vec![1, 2]);
+        assert_eq!(encode(two), vec![9]);
+    }
+
+    #[test]
+    fn default_omits_default_value() {
+        assert_eq!(encode(default(Raw(&[1]), Raw(&[1]))), Vec::<u8>::new());
+        assert_eq!(encode(default(Raw(&[2]), Raw(&[1]))), vec![2]);
+    }
+
+    #[test]
+    fn implicit_replaces_tag() {
+        // `5u8` is a universal INTEGER (tag 0x02) with content `[5]`;
+        // `implicit` should swap in the context-specific `[0]` tag while
+        // leaving the content untouched.
+        let tag = Tag::ctxt(0);
+        assert_eq!(encode(implicit(tag, 5u8)), vec![0x80, 0x01, 0x05]);
+    }
+}
@@ -2,10 +2,10 @@
 //!
 //! This is a private module. Its public content is being re-exported by the
 //! parent module.
-//!
-//! TODO: Add more useful things to these types.
 
 use bytes::Bytes;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use super::decode;
 use super::tag::Tag;
 use encode::PrimitiveContent;
@@ -62,6 +62,29 @@ impl Integer {
         }
         Ok(Integer(res))
     }
+
+    /// Takes an optional value of this type from the beginning of `cons`.
+    pub fn take_opt_from<S: decode::Source>(
+        cons: &mut decode::Constructed<S>
+    ) -> Result<Option<Self>, S::Err> {
+        cons.take_opt_primitive_if(Tag::INTEGER, Self::take_content_from)
+    }
+}
+
+impl PrimitiveContent for Integer {
+    const TAG: Tag = Tag::INTEGER;
+
+    fn encoded_len(&self, _mode: Mode) -> usize {
+        self.0.len()
+    }
+
+    fn write_encoded<W: io::Write>(
+        &self,
+        _mode: Mode,
+        target: &mut W
+    ) -> Result<(), io::Error> {
+        target.write_all(self.0.as_ref())
+    }
 }
 
 
@@ -117,6 +140,13 @@ impl Unsigned {
         }
         Ok(Unsigned(res))
     }
+
+    /// Takes an optional value of this type from the beginning of `cons`.
+    pub fn take_opt_from<S: decode::Source>(
+        cons: &mut decode::Constructed<S>
+    ) -> Result<Option<Self>, S::Err> {
+        cons.take_opt_primitive_if(Tag::INTEGER, Self::take_content_from)
+    }
 }
 
 impl PrimitiveContent for Unsigned {
@@ -135,10 +165,425 @@ impl PrimitiveContent for Unsigned {
     }
 }
 
-impl From<u32> for Unsigned {
-    fn from(n: u32) -> Self {
-        Unsigned(n.to_encoded_bytes(Mode::Der))
+/// A reference to a primitive content is itself one.
+///
+/// Mirrors `Values`’s blanket impl for `&'a T` in `encode`: it
+/// lets combinators such as `encode::implicit` be used with a borrowed
+/// field (`implicit(tag, &self.field)`) instead of requiring an owned
+/// value, which a method taking `&self` can’t produce.
+impl<'a, T: PrimitiveContent> PrimitiveContent for &'a T {
+    const TAG: Tag = T::TAG;
+
+    fn encoded_len(&self, mode: Mode) -> usize {
+        (**self).encoded_len(mode)
+    }
+
+    fn write_encoded<W: io::Write>(
+        &self,
+        mode: Mode,
+        target: &mut W
+    ) -> Result<(), io::Error> {
+        (**self).write_encoded(mode, target)
     }
 }
 
 
+//------------ Ordering -------------------------------------------------------
+
+/// Compares two big-endian, two’s complement byte sequences by value.
+///
+/// A sequence is negative iff it is non-empty and the most significant bit
+/// of its first octet is set. The shorter of the two sequences is
+/// sign-extended -- prepended with `0x00` octets if it is non-negative, or
+/// `0xFF` octets if it is negative -- to the length of the longer one.
+/// The now equal-length sequences are then compared by interpreting their
+/// first octet as a signed `i8` and, if those are equal, comparing the
+/// remaining octets lexicographically as unsigned bytes.
+fn cmp_signed(left: &[u8], right: &[u8]) -> Ordering {
+    fn is_negative(bytes: &[u8]) -> bool {
+        bytes.first().map(|&x| x & 0x80 != 0).unwrap_or(false)
+    }
+
+    fn extend(bytes: &[u8], len: usize) -> Vec<u8> {
+        let fill = if is_negative(bytes) { 0xFF } else { 0x00 };
+        let mut res = vec![fill; len - bytes.len()];
+        res.extend_from_slice(bytes);
+        res
+    }
+
+    let len = left.len().max(right.len());
+    let left = extend(left, len);
+    let right = extend(right, len);
+    match (left[0] as i8).cmp(&(right[0] as i8)) {
+        Ordering::Equal => left[1..].cmp(&right[1..]),
+        other => other,
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_signed(&self.0, &other.0)
+    }
+}
+
+impl PartialOrd for Unsigned {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Unsigned {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `Unsigned`’s content never has its sign bit set, so `cmp_signed`
+        // gives us correct unsigned ordering, too.
+        cmp_signed(&self.0, &other.0)
+    }
+}
+
+
+//------------ Conversions to and from Native Integers ------------------------
+
+/// Trims redundant sign-extension octets off a big-endian, two’s
+/// complement byte sequence, leaving at least one octet.
+///
+/// A leading octet is redundant if it is `0x00` and the following octet’s
+/// most significant bit is unset, or if it is `0xFF` and the following
+/// octet’s most significant bit is set -- in both cases, dropping it
+/// doesn’t change the value or its sign.
+fn trim_sign(bytes: &[u8]) -> &[u8] {
+    let mut start = 0;
+    while start + 1 < bytes.len() {
+        let cur = bytes[start];
+        let next_msb = bytes[start + 1] & 0x80 != 0;
+        if (cur == 0x00 && !next_msb) || (cur == 0xFF && next_msb) {
+            start += 1;
+        }
+        else {
+            break
+        }
+    }
+    &bytes[start..]
+}
+
+macro_rules! impl_signed_content {
+    ( $($int:ty),* $(,)? ) => {
+        $(
+            impl PrimitiveContent for $int {
+                const TAG: Tag = Tag::INTEGER;
+
+                fn encoded_len(&self, _mode: Mode) -> usize {
+                    trim_sign(&self.to_be_bytes()).len()
+                }
+
+                fn write_encoded<W: io::Write>(
+                    &self,
+                    _mode: Mode,
+                    target: &mut W
+                ) -> Result<(), io::Error> {
+                    target.write_all(trim_sign(&self.to_be_bytes()))
+                }
+            }
+        )*
+    }
+}
+
+impl_signed_content!(i8, i16, i32, i64, i128);
+
+macro_rules! impl_unsigned_content {
+    ( $( ($int:ty, $len:expr) ),* $(,)? ) => {
+        $(
+            impl PrimitiveContent for $int {
+                const TAG: Tag = Tag::INTEGER;
+
+                fn encoded_len(&self, _mode: Mode) -> usize {
+                    let mut buf = [0u8; $len + 1];
+                    buf[1..].copy_from_slice(&self.to_be_bytes());
+                    trim_sign(&buf).len()
+                }
+
+                fn write_encoded<W: io::Write>(
+                    &self,
+                    _mode: Mode,
+                    target: &mut W
+                ) -> Result<(), io::Error> {
+                    let mut buf = [0u8; $len + 1];
+                    buf[1..].copy_from_slice(&self.to_be_bytes());
+                    target.write_all(trim_sign(&buf))
+                }
+            }
+        )*
+    }
+}
+
+impl_unsigned_content! {
+    (u8, 1), (u16, 2), (u32, 4), (u64, 8), (u128, 16),
+}
+
+macro_rules! impl_from_native {
+    ( $target:ident; $($int:ty),* $(,)? ) => {
+        $(
+            impl From<$int> for $target {
+                fn from(n: $int) -> Self {
+                    $target(n.to_encoded_bytes(Mode::Der))
+                }
+            }
+        )*
+    }
+}
+
+impl_from_native!(Integer; i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+impl_from_native!(Unsigned; u8, u16, u32, u64, u128);
+
+/// Sign-extends `bytes` -- a minimal, big-endian, two’s complement
+/// encoding -- into an `i128`, failing if it doesn’t fit.
+fn signed_to_i128(bytes: &[u8]) -> Result<i128, decode::Error> {
+    if bytes.len() > 16 {
+        xerr!(return Err(decode::Error::Malformed))
+    }
+    let sign = if bytes.first().map(|&x| x & 0x80 != 0).unwrap_or(false) {
+        0xFF
+    }
+    else {
+        0x00
+    };
+    let mut buf = [sign; 16];
+    let start = 16 - bytes.len();
+    buf[start..].copy_from_slice(bytes);
+    Ok(i128::from_be_bytes(buf))
+}
+
+/// Zero-extends `bytes` -- a minimal, big-endian encoding -- into a
+/// `u128`, failing if it doesn’t fit.
+///
+/// Unlike signed values, the minimal encoding of an unsigned value whose
+/// most significant bit is set needs one extra leading `0x00` octet (so
+/// that it isn’t mistaken for a negative two’s complement value), so a
+/// 17-octet encoding is legitimate as long as that leading octet is zero.
+fn unsigned_to_u128(bytes: &[u8]) -> Result<u128, decode::Error> {
+    let bytes = match bytes.len() {
+        17 if bytes[0] == 0 => &bytes[1..],
+        len if len > 16 => {
+            xerr!(return Err(decode::Error::Malformed))
+        }
+        _ => bytes,
+    };
+    let mut buf = [0u8; 16];
+    let start = 16 - bytes.len();
+    buf[start..].copy_from_slice(bytes);
+    Ok(u128::from_be_bytes(buf))
+}
+
+macro_rules! impl_int_conversions {
+    ( $( ($signed:ty, $unsigned:ty,
+          $to_signed:ident, $to_unsigned:ident,
+          $take_signed:ident, $take_unsigned:ident) ),* $(,)?
+    ) => {
+        /// # Converting into Native Integers
+        impl Integer {
+            $(
+                pub fn $to_signed(&self) -> Result<$signed, decode::Error> {
+                    signed_to_i128(&self.0).and_then(|value| {
+                        <$signed>::try_from(value).map_err(|_| {
+                            decode::Error::Malformed
+                        })
+                    })
+                }
+
+                pub fn $to_unsigned(&self) -> Result<$unsigned, decode::Error> {
+                    // `i128` can’t represent the top half of `u128`, so
+                    // for unsigned targets we check the sign bit and,
+                    // for non-negative content, decode via
+                    // `unsigned_to_u128` instead of bouncing through
+                    // `i128`.
+                    if self.0.first().map(|&x| x & 0x80 != 0).unwrap_or(false) {
+                        xerr!(return Err(decode::Error::Malformed))
+                    }
+                    unsigned_to_u128(&self.0).and_then(|value| {
+                        <$unsigned>::try_from(value).map_err(|_| {
+                            decode::Error::Malformed
+                        })
+                    })
+                }
+            )*
+        }
+
+        /// # Decoding Into Native Integers
+        impl Integer {
+            $(
+                pub fn $take_signed<S: decode::Source>(
+                    cons: &mut decode::Constructed<S>
+                ) -> Result<$signed, S::Err> {
+                    Self::take_from(cons)?.$to_signed().map_err(Into::into)
+                }
+
+                pub fn $take_unsigned<S: decode::Source>(
+                    cons: &mut decode::Constructed<S>
+                ) -> Result<$unsigned, S::Err> {
+                    Self::take_from(cons)?.$to_unsigned().map_err(Into::into)
+                }
+            )*
+        }
+
+        /// # Converting into Native Integers
+        impl Unsigned {
+            $(
+                pub fn $to_unsigned(&self) -> Result<$unsigned, decode::Error> {
+                    unsigned_to_u128(&self.0).and_then(|value| {
+                        <$unsigned>::try_from(value).map_err(|_| {
+                            decode::Error::Malformed
+                        })
+                    })
+                }
+
+                pub fn $to_signed(&self) -> Result<$signed, decode::Error> {
+                    unsigned_to_u128(&self.0).and_then(|value| {
+                        <$signed>::try_from(value).map_err(|_| {
+                            decode::Error::Malformed
+                        })
+                    })
+                }
+            )*
+        }
+
+        /// # Decoding Into Native Integers
+        impl Unsigned {
+            $(
+                pub fn $take_unsigned<S: decode::Source>(
+                    cons: &mut decode::Constructed<S>
+                ) -> Result<$unsigned, S::Err> {
+                    Self::take_from(cons)?.$to_unsigned().map_err(Into::into)
+                }
+
+                pub fn $take_signed<S: decode::Source>(
+                    cons: &mut decode::Constructed<S>
+                ) -> Result<$signed, S::Err> {
+                    Self::take_from(cons)?.$to_signed().map_err(Into::into)
+                }
+            )*
+        }
+    }
+}
+
+impl_int_conversions! {
+    (i8, u8, to_i8, to_u8, take_i8, take_u8),
+    (i16, u16, to_i16, to_u16, take_i16, take_u16),
+    (i32, u32, to_i32, to_u32, take_i32, take_u32),
+    (i64, u64, to_i64, to_u64, take_i64, take_u64),
+    (i128, u128, to_i128, to_u128, take_i128, take_u128),
+}
+
+
+//------------ Bridging to `num-bigint` ---------------------------------------
+//
+// The following items are only available if the `bigint` feature is
+// enabled. They bridge `Integer` and `Unsigned` to the arbitrary-precision
+// types of the `num-bigint` crate for values that don’t fit any native
+// integer type, such as RSA moduli or EC coordinates.
+
+#[cfg(feature = "bigint")]
+use num_bigint::{BigInt, BigUint};
+
+#[cfg(feature = "bigint")]
+impl Integer {
+    /// Converts the integer into an arbitrary-precision `BigInt`.
+    pub fn to_bigint(&self) -> BigInt {
+        BigInt::from_signed_bytes_be(&self.0)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Unsigned {
+    /// Converts the integer into an arbitrary-precision `BigUint`.
+    pub fn to_biguint(&self) -> BigUint {
+        BigUint::from_bytes_be(&self.0)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl<'a> From<&'a BigInt> for Integer {
+    fn from(n: &'a BigInt) -> Self {
+        // `BigInt::to_signed_bytes_be` already produces the minimal,
+        // big-endian, two’s complement encoding BER requires.
+        Integer(n.to_signed_bytes_be().into())
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl<'a> From<&'a BigUint> for Unsigned {
+    fn from(n: &'a BigUint) -> Self {
+        // `BigUint::to_bytes_be` strips leading zero octets but doesn’t
+        // know about BER’s sign bit, so we may have to add one back.
+        let bytes = n.to_bytes_be();
+        let mut buf = Vec::with_capacity(bytes.len() + 1);
+        buf.push(0);
+        buf.extend_from_slice(&bytes);
+        Unsigned(trim_sign(&buf).to_vec().into())
+    }
+}
+
+
+//------------ Tests --------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsigned_native_round_trip() {
+        assert_eq!(Unsigned::from(0u128).to_u128(), Ok(0));
+        assert_eq!(Unsigned::from(u64::MAX).to_u64(), Ok(u64::MAX));
+
+        // The minimal BER encoding of a value whose top bit is set needs
+        // a leading `0x00` octet, growing the content to 17 octets for
+        // values in `[2^127, 2^128)`; this must still round-trip.
+        assert_eq!(Unsigned::from(u128::MAX).to_u128(), Ok(u128::MAX));
+    }
+
+    #[test]
+    fn signed_native_round_trip() {
+        assert_eq!(Integer::from(0i128).to_i128(), Ok(0));
+        assert_eq!(Integer::from(i128::MIN).to_i128(), Ok(i128::MIN));
+        assert_eq!(Integer::from(i128::MAX).to_i128(), Ok(i128::MAX));
+    }
+
+    #[test]
+    fn integer_unsigned_extraction_round_trip() {
+        // The minimal encoding of `u128::MAX` needs a leading `0x00` to
+        // keep it non-negative, just like `Unsigned`’s; and the value
+        // itself is beyond what an `i128` intermediate can represent.
+        assert_eq!(Integer::from(u128::MAX).to_u128(), Ok(u128::MAX));
+        assert_eq!(Integer::from(u64::MAX).to_u64(), Ok(u64::MAX));
+        assert!(Integer::from(-1i64).to_u64().is_err());
+    }
+
+    #[test]
+    fn integer_ord_matches_value() {
+        assert!(Integer::from(-1i64) < Integer::from(0i64));
+        assert!(Integer::from(0i64) < Integer::from(1i64));
+        assert!(Integer::from(i64::MIN) < Integer::from(i64::MAX));
+    }
+
+    #[test]
+    fn unsigned_ord_matches_value() {
+        assert!(Unsigned::from(0u64) < Unsigned::from(1u64));
+        assert!(Unsigned::from(254u64) < Unsigned::from(255u64));
+        assert!(Unsigned::from(u64::MAX) > Unsigned::from(0u64));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn bigint_round_trip() {
+        // A value well beyond any native integer type.
+        let big = BigInt::from(i128::MAX) * BigInt::from(1000);
+        assert_eq!(Integer::from(&big).to_bigint(), big);
+
+        let big = BigUint::from(u128::MAX) * BigUint::from(1000u32);
+        assert_eq!(Unsigned::from(&big).to_biguint(), big);
+    }
+}
@@ -0,0 +1,133 @@
+//! Integration tests for `#[derive(Encode)]` / `#[derive(Decode)]`.
+
+extern crate bcder;
+
+use bcder::{decode, Mode};
+use bcder::encode::Values;
+use bcder::{Encode, Decode};
+
+/// A trivial struct using implicit tagging on a primitive field, the
+/// headline use case for `#[ber(tag = "...", implicit)]`.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode)]
+struct Greeting {
+    #[ber(tag = "context(0)", implicit)]
+    code: bcder::Unsigned,
+}
+
+#[test]
+fn implicit_field_round_trips() {
+    let greeting = Greeting { code: bcder::Unsigned::from(12u32) };
+
+    let mut buf = Vec::new();
+    greeting.encode().write_encoded(Mode::Der, &mut buf).unwrap();
+
+    let decoded = decode::Constructed::decode(
+        buf.as_slice(), Mode::Der, Greeting::take_from
+    ).unwrap();
+
+    assert_eq!(decoded, greeting);
+}
+
+/// A struct using explicit tagging, i.e. `tag = "..."` without
+/// `implicit`.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode)]
+struct Wrapped {
+    #[ber(tag = "context(1)")]
+    code: bcder::Unsigned,
+}
+
+#[test]
+fn explicit_tag_round_trips() {
+    let wrapped = Wrapped { code: bcder::Unsigned::from(7u32) };
+
+    let mut buf = Vec::new();
+    wrapped.encode().write_encoded(Mode::Der, &mut buf).unwrap();
+
+    let decoded = decode::Constructed::decode(
+        buf.as_slice(), Mode::Der, Wrapped::take_from
+    ).unwrap();
+
+    assert_eq!(decoded, wrapped);
+}
+
+/// A struct with a plain `Option<T>` OPTIONAL field.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode)]
+struct Optional {
+    code: Option<bcder::Unsigned>,
+}
+
+#[test]
+fn option_field_round_trips_present_and_absent() {
+    for code in [Some(bcder::Unsigned::from(3u32)), None] {
+        let value = Optional { code };
+
+        let mut buf = Vec::new();
+        value.encode().write_encoded(Mode::Der, &mut buf).unwrap();
+
+        let decoded = decode::Constructed::decode(
+            buf.as_slice(), Mode::Der, Optional::take_from
+        ).unwrap();
+
+        assert_eq!(decoded, value);
+    }
+}
+
+fn default_code() -> bcder::Unsigned {
+    bcder::Unsigned::from(0u32)
+}
+
+/// A struct with an `OPTIONAL DEFAULT` field.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode)]
+struct Defaulted {
+    #[ber(default = "default_code")]
+    code: bcder::Unsigned,
+}
+
+#[test]
+fn default_field_omits_default_value_but_round_trips() {
+    let default = Defaulted { code: default_code() };
+    let mut buf = Vec::new();
+    default.encode().write_encoded(Mode::Der, &mut buf).unwrap();
+    // Only the field is omitted; the empty SEQUENCE itself remains.
+    assert_eq!(buf, vec![0x30, 0x00]);
+    let decoded = decode::Constructed::decode(
+        buf.as_slice(), Mode::Der, Defaulted::take_from
+    ).unwrap();
+    assert_eq!(decoded, default);
+
+    let non_default = Defaulted { code: bcder::Unsigned::from(5u32) };
+    let mut buf = Vec::new();
+    non_default.encode().write_encoded(Mode::Der, &mut buf).unwrap();
+    assert!(!buf.is_empty());
+    let decoded = decode::Constructed::decode(
+        buf.as_slice(), Mode::Der, Defaulted::take_from
+    ).unwrap();
+    assert_eq!(decoded, non_default);
+}
+
+/// A struct with several fields, to verify that `encode`/
+/// `take_content_from` process them in declaration order.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode)]
+struct Multi {
+    first: bcder::Unsigned,
+    second: bcder::Unsigned,
+    third: bcder::Unsigned,
+}
+
+#[test]
+fn multi_field_struct_preserves_order() {
+    let multi = Multi {
+        first: bcder::Unsigned::from(1u32),
+        second: bcder::Unsigned::from(2u32),
+        third: bcder::Unsigned::from(3u32),
+    };
+
+    let mut buf = Vec::new();
+    multi.encode().write_encoded(Mode::Der, &mut buf).unwrap();
+
+    let decoded = decode::Constructed::decode(
+        buf.as_slice(), Mode::Der, Multi::take_from
+    ).unwrap();
+
+    assert_eq!(decoded, multi);
+}